@@ -0,0 +1,27 @@
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::tray;
+
+/// Whether Invoices is currently set to launch at login.
+#[tauri::command]
+pub fn get_autostart_enabled(app: AppHandle) -> Result<bool, String> {
+    app.autolaunch().is_enabled().map_err(|err| err.to_string())
+}
+
+/// Turns launch-at-login on or off.
+#[tauri::command]
+pub fn set_autostart(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|err| err.to_string())?;
+
+    // Keep the tray's "Start Invoices at login" checkbox in sync with the
+    // new state instead of waiting for the next periodic/focus refresh.
+    tray::refresh_menu_now(&app);
+    Ok(())
+}
@@ -1,78 +1,82 @@
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
-    Manager,
-};
+mod autostart;
+mod sidecar;
+mod tray;
+mod updater;
 
-#[tauri::command]
-fn get_sidecar_port() -> u16 {
-    // In production, the sidecar writes its port to a temp file.
-    // For dev, we default to 3001.
-    let port_file = std::env::temp_dir().join("invoices-sidecar-port");
-    if let Ok(port_str) = std::fs::read_to_string(&port_file) {
-        port_str.trim().parse().unwrap_or(3001)
-    } else {
-        3001
-    }
+use serde::Serialize;
+use sidecar::SidecarState;
+use tauri::{Emitter, Manager};
+use tray::TrayState;
+use updater::UpdaterState;
+
+/// Payload forwarded from a second launch to the primary instance, so it can
+/// act on CLI arguments/file paths the same way it would if they'd been
+/// passed to it directly.
+#[derive(Clone, Serialize)]
+struct SingleInstancePayload {
+    args: Vec<String>,
+    cwd: String,
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Must be registered first: on a second launch this focuses the
+        // existing window and lets the new process exit before `setup`
+        // spawns a competing sidecar.
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let _ = app.emit(
+                "single-instance://args",
+                SingleInstancePayload { args, cwd },
+            );
+        }))
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             None,
         ))
-        .invoke_handler(tauri::generate_handler![get_sidecar_port])
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .manage(SidecarState::default())
+        .manage(TrayState::default())
+        .manage(UpdaterState::default())
+        .invoke_handler(tauri::generate_handler![
+            sidecar::get_sidecar_port,
+            updater::check_for_updates,
+            updater::install_update_and_restart,
+            autostart::get_autostart_enabled,
+            autostart::set_autostart,
+        ])
         .setup(|app| {
-            // Build system tray
-            let quit = MenuItem::with_id(app, "quit", "Quit Invoices", true, None::<&str>)?;
-            let show = MenuItem::with_id(app, "show", "Open Invoices", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show, &quit])?;
-
-            TrayIconBuilder::new()
-                .menu(&menu)
-                .tooltip("Invoices – Kontrolní hlášení DPH")
-                .icon(app.default_window_icon().unwrap().clone())
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    _ => {}
-                })
-                .build(app)?;
+            tray::setup_tray(app.handle())?;
+            updater::spawn_update_checker(app.handle());
 
-            // Hide window to tray on close instead of quitting
+            // Hide window to tray on close instead of quitting, and refresh
+            // the tray menu whenever the window regains focus so the
+            // recent-invoices list doesn't go stale.
             let window = app.get_webview_window("main").unwrap();
             let w = window.clone();
-            window.on_window_event(move |event| {
-                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+            let app_handle = app.handle().clone();
+            window.on_window_event(move |event| match event {
+                tauri::WindowEvent::CloseRequested { api, .. } => {
                     api.prevent_close();
                     let _ = w.hide();
                 }
+                tauri::WindowEvent::Focused(true) => {
+                    tray::on_window_focus(&app_handle);
+                }
+                _ => {}
             });
 
-            // Spawn Bun sidecar in production
+            // Spawn Bun sidecar in production, keeping it alive (with
+            // auto-restart) for as long as the app runs.
             // In dev mode, sidecar runs separately via `bun run --watch`
             #[cfg(not(debug_assertions))]
-            {
-                let sidecar_command = app
-                    .shell()
-                    .sidecar("sidecar/invoices-sidecar")
-                    .expect("failed to create sidecar command");
-
-                let (_rx, _child) = sidecar_command
-                    .spawn()
-                    .expect("Failed to spawn sidecar");
-            }
+            sidecar::spawn_sidecar(app.handle());
 
             Ok(())
         })
@@ -0,0 +1,148 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+/// Prefix the Bun sidecar writes to stdout once its HTTP server is listening,
+/// e.g. `SIDECAR_PORT=3001`.
+const PORT_HANDSHAKE_PREFIX: &str = "SIDECAR_PORT=";
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// A sidecar that stays up at least this long is considered healthy again,
+/// so a later crash restarts the backoff from `INITIAL_BACKOFF`.
+const HEALTHY_UPTIME: Duration = Duration::from_secs(60);
+
+/// Shared state tracking the currently running sidecar process and the port
+/// it reported over stdout. Managed via `Manager::manage` so commands and the
+/// restart loop can both reach it.
+#[derive(Default)]
+pub struct SidecarState {
+    port: Mutex<Option<u16>>,
+    child: Mutex<Option<CommandChild>>,
+    /// Set by `shutdown()` so the auto-restart supervisor in `spawn_sidecar`
+    /// stays down instead of spawning a replacement process.
+    stopped: AtomicBool,
+}
+
+impl SidecarState {
+    pub fn port(&self) -> Option<u16> {
+        *self.port.lock().unwrap()
+    }
+
+    /// Stops the running sidecar, if any, by killing its process, and tells
+    /// the supervisor loop to stop respawning it. No shutdown signal is sent
+    /// first, so the sidecar gets no chance to clean up. Used before
+    /// installing an update so a
+    /// freshly-resurrected sidecar doesn't hold the port/binary open and
+    /// fight the installer.
+    pub fn shutdown(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        if let Some(child) = self.child.lock().unwrap().take() {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// Spawn the Bun sidecar and keep it alive for the lifetime of the app.
+///
+/// Reads the handshake port off stdout instead of the old temp-file
+/// (`invoices-sidecar-port`) approach, which could go stale or be missing
+/// entirely. If the sidecar exits unexpectedly it is respawned with
+/// exponential backoff, and the frontend is notified of the new port via the
+/// `sidecar://port` event each time it changes.
+pub fn spawn_sidecar(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            if app.state::<SidecarState>().stopped.load(Ordering::SeqCst) {
+                return;
+            }
+            let started_at = std::time::Instant::now();
+            spawn_and_wait(&app).await;
+            if app.state::<SidecarState>().stopped.load(Ordering::SeqCst) {
+                return;
+            }
+            if started_at.elapsed() >= HEALTHY_UPTIME {
+                backoff = INITIAL_BACKOFF;
+            } else {
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            tokio::time::sleep(backoff).await;
+        }
+    });
+}
+
+/// Spawns one instance of the sidecar and drives its event stream until it
+/// terminates (or `SidecarState::shutdown` fires).
+async fn spawn_and_wait(app: &AppHandle) {
+    let sidecar_command = match app.shell().sidecar("sidecar/invoices-sidecar") {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("failed to create sidecar command: {err}");
+            return;
+        }
+    };
+
+    let (mut rx, child) = match sidecar_command.spawn() {
+        Ok(pair) => pair,
+        Err(err) => {
+            eprintln!("failed to spawn sidecar: {err}");
+            return;
+        }
+    };
+
+    let state = app.state::<SidecarState>();
+    *state.child.lock().unwrap() = Some(child);
+
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(line) => {
+                let line = String::from_utf8_lossy(&line);
+                if let Some(port) = parse_port_handshake(&line) {
+                    *app.state::<SidecarState>().port.lock().unwrap() = Some(port);
+                    let _ = app.emit("sidecar://port", port);
+                }
+            }
+            CommandEvent::Stderr(line) => {
+                eprintln!("sidecar stderr: {}", String::from_utf8_lossy(&line));
+            }
+            CommandEvent::Terminated(payload) => {
+                eprintln!("sidecar terminated: {payload:?}");
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let state = app.state::<SidecarState>();
+    *state.port.lock().unwrap() = None;
+    *state.child.lock().unwrap() = None;
+}
+
+fn parse_port_handshake(line: &str) -> Option<u16> {
+    line.trim().strip_prefix(PORT_HANDSHAKE_PREFIX)?.parse().ok()
+}
+
+/// The port to assume when no sidecar has checked in yet, e.g. during local
+/// development, where the sidecar runs separately via `bun run --watch`
+/// instead of being spawned (and handshaking its port) by `spawn_sidecar`.
+const DEV_DEFAULT_PORT: u16 = 3001;
+
+/// Resolves the port to talk to the sidecar on, falling back to
+/// [`DEV_DEFAULT_PORT`] so callers work the same in `cargo tauri dev` as in a
+/// production build.
+pub fn resolve_port(state: &SidecarState) -> u16 {
+    state.port().unwrap_or(DEV_DEFAULT_PORT)
+}
+
+/// Returns the port the running sidecar reported, falling back to the dev
+/// default when no sidecar has checked in yet.
+#[tauri::command]
+pub fn get_sidecar_port(state: tauri::State<'_, SidecarState>) -> u16 {
+    resolve_port(&state)
+}
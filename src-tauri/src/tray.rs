@@ -0,0 +1,263 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::Deserialize;
+use tauri::{
+    menu::{CheckMenuItem, Menu, MenuItem, Submenu},
+    tray::TrayIcon,
+    tray::TrayIconBuilder,
+    AppHandle, Emitter, Manager,
+};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::sidecar::{self, SidecarState};
+use crate::updater::{self, UpdateStatus, UpdaterState};
+
+/// How many recent invoices to list under the "Recent invoices" submenu.
+const RECENT_INVOICES_LIMIT: usize = 5;
+/// How often the menu is rebuilt in the background, in addition to the
+/// rebuild that happens whenever the main window regains focus.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Bound on the recent-invoices request so a hung sidecar can't freeze the
+/// periodic refresh loop forever.
+const SIDECAR_REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+const DEFAULT_TOOLTIP: &str = "Invoices – Kontrolní hlášení DPH";
+
+#[derive(Deserialize)]
+struct RecentInvoice {
+    id: String,
+    number: String,
+    period: String,
+}
+
+/// Holds the tray icon once built so the menu can be rebuilt in place later
+/// via `TrayIcon::set_menu` instead of tearing down and recreating the tray.
+#[derive(Default)]
+pub struct TrayState(Mutex<Option<TrayIcon>>);
+
+/// Builds the tray icon with its initial (static-only) menu, wires up the
+/// menu event handler, and kicks off the periodic refresh that keeps the
+/// "Recent invoices" submenu current.
+pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app, &[])?;
+
+    let tray = TrayIconBuilder::new()
+        .menu(&menu)
+        .tooltip(tooltip_for_state(app))
+        .icon(app.default_window_icon().unwrap().clone())
+        .on_menu_event(on_menu_event)
+        .build(app)?;
+
+    *app.state::<TrayState>().0.lock().unwrap() = Some(tray);
+
+    let app_for_refresh = app.clone();
+    tauri::async_runtime::spawn(async move {
+        refresh_menu(&app_for_refresh).await;
+
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        interval.tick().await; // first tick fires immediately, skip it
+        loop {
+            interval.tick().await;
+            refresh_menu(&app_for_refresh).await;
+        }
+    });
+
+    Ok(())
+}
+
+/// Rebuilds the tray menu, e.g. in response to the main window regaining
+/// focus, so the recent-invoices list doesn't go stale while the app sits in
+/// the tray.
+pub fn on_window_focus(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        refresh_menu(&app).await;
+    });
+}
+
+/// Rebuilds the tray menu right away, e.g. after an update check changes
+/// whether "Install update and restart" should be shown.
+pub fn refresh_menu_now(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        refresh_menu(&app).await;
+    });
+}
+
+async fn refresh_menu(app: &AppHandle) {
+    let recent = fetch_recent_invoices(app).await.unwrap_or_default();
+    let menu = match build_menu(app, &recent) {
+        Ok(menu) => menu,
+        Err(err) => {
+            eprintln!("failed to rebuild tray menu: {err}");
+            return;
+        }
+    };
+
+    if let Some(tray) = app.state::<TrayState>().0.lock().unwrap().as_ref() {
+        if let Err(err) = tray.set_menu(Some(menu)) {
+            eprintln!("failed to apply tray menu: {err}");
+        }
+        if let Err(err) = tray.set_tooltip(Some(tooltip_for_state(app))) {
+            eprintln!("failed to apply tray tooltip: {err}");
+        }
+    }
+}
+
+/// Tooltip reflecting the current update status, so an available update is
+/// visible at a glance without opening the menu.
+fn tooltip_for_state(app: &AppHandle) -> String {
+    match app.state::<UpdaterState>().current() {
+        Some(UpdateStatus::Available { version }) => {
+            format!("{DEFAULT_TOOLTIP} — update {version} available")
+        }
+        _ => DEFAULT_TOOLTIP.to_string(),
+    }
+}
+
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(SIDECAR_REQUEST_TIMEOUT)
+            .build()
+            .expect("failed to build sidecar http client")
+    })
+}
+
+async fn fetch_recent_invoices(app: &AppHandle) -> Option<Vec<RecentInvoice>> {
+    let port = sidecar::resolve_port(&app.state::<SidecarState>());
+    let url = format!("http://127.0.0.1:{port}/api/invoices?limit={RECENT_INVOICES_LIMIT}");
+    match http_client().get(url).send().await {
+        Ok(response) => response.json::<Vec<RecentInvoice>>().await.ok(),
+        Err(err) => {
+            eprintln!("failed to fetch recent invoices from sidecar: {err}");
+            None
+        }
+    }
+}
+
+fn build_menu(app: &AppHandle, recent: &[RecentInvoice]) -> tauri::Result<Menu<tauri::Wry>> {
+    let show = MenuItem::with_id(app, "show", "Open Invoices", true, None::<&str>)?;
+    let generate_kh = MenuItem::with_id(
+        app,
+        "generate-kh",
+        "Generate Kontrolní hlášení for current period",
+        true,
+        None::<&str>,
+    )?;
+    let export_xml = MenuItem::with_id(app, "export-xml", "Export XML", true, None::<&str>)?;
+    let check_for_updates =
+        MenuItem::with_id(app, "check-for-updates", "Check for updates…", true, None::<&str>)?;
+    let autostart_enabled = app.autolaunch().is_enabled().unwrap_or(false);
+    let toggle_autostart = CheckMenuItem::with_id(
+        app,
+        "toggle-autostart",
+        "Start Invoices at login",
+        true,
+        autostart_enabled,
+        None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(app, "quit", "Quit Invoices", true, None::<&str>)?;
+
+    let install_update = match app.state::<UpdaterState>().current() {
+        Some(UpdateStatus::Available { version }) => Some(MenuItem::with_id(
+            app,
+            "install-update",
+            format!("Install update {version} and restart"),
+            true,
+            None::<&str>,
+        )?),
+        _ => None,
+    };
+
+    let recent_items: Vec<MenuItem<tauri::Wry>> = recent
+        .iter()
+        .map(|invoice| {
+            MenuItem::with_id(
+                app,
+                format!("open-invoice:{}", invoice.id),
+                format!("{} ({})", invoice.number, invoice.period),
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<_>>()?;
+
+    let recent_submenu = if recent_items.is_empty() {
+        let placeholder =
+            MenuItem::with_id(app, "no-recent-invoices", "No recent invoices", false, None::<&str>)?;
+        Submenu::with_items(app, "Recent invoices", true, &[&placeholder])?
+    } else {
+        let refs: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = recent_items
+            .iter()
+            .map(|item| item as &dyn tauri::menu::IsMenuItem<tauri::Wry>)
+            .collect();
+        Submenu::with_items(app, "Recent invoices", true, &refs)?
+    };
+
+    let mut items: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = vec![
+        &show,
+        &recent_submenu,
+        &generate_kh,
+        &export_xml,
+        &check_for_updates,
+        &toggle_autostart,
+    ];
+    if let Some(install_update) = &install_update {
+        items.push(install_update);
+    }
+    items.push(&quit);
+
+    Menu::with_items(app, &items)
+}
+
+fn on_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
+    let id = event.id.as_ref();
+    if let Some(invoice_id) = id.strip_prefix("open-invoice:") {
+        let _ = app.emit("tray://open-invoice", invoice_id);
+        show_main_window(app);
+        return;
+    }
+
+    match id {
+        "quit" => app.exit(0),
+        "show" => show_main_window(app),
+        "generate-kh" => {
+            let _ = app.emit("tray://generate-kontrolni-hlaseni", ());
+            show_main_window(app);
+        }
+        "export-xml" => {
+            let _ = app.emit("tray://export-xml", ());
+            show_main_window(app);
+        }
+        "check-for-updates" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                updater::check_now(&app).await;
+            });
+        }
+        "install-update" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = updater::install_update_and_restart(app).await {
+                    eprintln!("failed to install update: {err}");
+                }
+            });
+        }
+        "toggle-autostart" => {
+            let enabled = app.autolaunch().is_enabled().unwrap_or(false);
+            if let Err(err) = crate::autostart::set_autostart(app.clone(), !enabled) {
+                eprintln!("failed to toggle autostart: {err}");
+            }
+        }
+        _ => {}
+    }
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
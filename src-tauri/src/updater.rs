@@ -0,0 +1,107 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_updater::UpdaterExt;
+
+use crate::sidecar::SidecarState;
+use crate::tray;
+
+/// How often to check for updates in the background, in addition to the
+/// check that runs once on startup.
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "state", rename_all = "kebab-case")]
+pub enum UpdateStatus {
+    Checking,
+    Available { version: String },
+    UpToDate,
+    Error { message: String },
+}
+
+/// Last known update status, surfaced to the frontend via the
+/// `updater://status` event and read by the tray to decide whether to show
+/// "Install update and restart".
+#[derive(Default)]
+pub struct UpdaterState(Mutex<Option<UpdateStatus>>);
+
+impl UpdaterState {
+    pub fn current(&self) -> Option<UpdateStatus> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// Checks for updates once on startup, then on a recurring interval for as
+/// long as the app runs.
+pub fn spawn_update_checker(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        check_now(&app).await;
+
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        interval.tick().await; // first tick fires immediately, skip it
+        loop {
+            interval.tick().await;
+            check_now(&app).await;
+        }
+    });
+}
+
+/// Runs one update check, updates `UpdaterState`, emits the new status to the
+/// frontend, and refreshes the tray menu so "Install update and restart"
+/// appears or disappears as appropriate.
+pub async fn check_now(app: &AppHandle) {
+    set_status(app, UpdateStatus::Checking);
+
+    let status = match app.updater() {
+        Ok(updater) => match updater.check().await {
+            Ok(Some(update)) => UpdateStatus::Available {
+                version: update.version,
+            },
+            Ok(None) => UpdateStatus::UpToDate,
+            Err(err) => UpdateStatus::Error {
+                message: err.to_string(),
+            },
+        },
+        Err(err) => UpdateStatus::Error {
+            message: err.to_string(),
+        },
+    };
+
+    set_status(app, status);
+}
+
+fn set_status(app: &AppHandle, status: UpdateStatus) {
+    *app.state::<UpdaterState>().0.lock().unwrap() = Some(status.clone());
+    let _ = app.emit("updater://status", status);
+    tray::refresh_menu_now(app);
+}
+
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<(), String> {
+    check_now(&app).await;
+    Ok(())
+}
+
+/// Stops the sidecar (killing its process), downloads and applies the pending
+/// update, then restarts the app.
+#[tauri::command]
+pub async fn install_update_and_restart(app: AppHandle) -> Result<(), String> {
+    let updater = app.updater().map_err(|err| err.to_string())?;
+    let update = updater
+        .check()
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or("no update available")?;
+
+    app.state::<SidecarState>().shutdown();
+
+    update
+        .download_and_install(|_chunk, _total| {}, || {})
+        .await
+        .map_err(|err| err.to_string())?;
+
+    app.restart();
+}